@@ -1,4 +1,11 @@
-use std::{fs, io::Read, path::PathBuf, process::ExitCode, time::Duration, vec};
+use std::{
+    fs,
+    io::{BufRead, Write},
+    path::PathBuf,
+    process::ExitCode,
+    time::Duration,
+    vec,
+};
 
 use anyhow::{anyhow, bail};
 use clap::{Args, Parser, Subcommand};
@@ -7,9 +14,18 @@ use rand::seq::SliceRandom;
 
 use crate::{
     config::{CategoryConfig, GlobalConfig},
-    image_supplier::{ImageSupplier, SearchParameters, UrlSupplier},
+    dedup::History,
+    image_processing::{Color, FitMode, ProcessOptions, Resolution},
+    image_supplier::{Image, ImageSupplier, SearchParameters, UrlSupplier},
+    terminal,
 };
 
+/// Default number of entries kept in the dedup history.
+const DEFAULT_HISTORY_SIZE: usize = 50;
+/// Default number of times to re-query a supplier before accepting a
+/// duplicate wallpaper.
+const DEFAULT_DEDUP_RETRIES: usize = 5;
+
 #[derive(Args, Clone, Debug)]
 pub struct FetchArgs {
     #[arg(short, long)]
@@ -33,9 +49,39 @@ pub struct FetchArgs {
     #[arg(long)]
     /// Only return the images final path, for use in scripts.
     simple: bool,
+    #[arg(long)]
+    /// Resize the fetched wallpaper to fit this resolution, e.g. "1920x1080".
+    resize: Option<Resolution>,
+    #[arg(long)]
+    /// How to fit the wallpaper to `--resize`, defaults to "cover".
+    fit: Option<FitMode>,
+    #[arg(long)]
+    /// Apply a Gaussian blur pass with this sigma, for lock-screen style outputs.
+    blur: Option<f32>,
+    #[arg(long)]
+    /// Canvas color to pad with under `--fit contain`, as hex e.g. "000000"
+    /// or "00000080". Defaults to opaque black.
+    background: Option<Color>,
+    #[arg(long)]
+    /// Skip the dedup check, allowing recently seen wallpapers to repeat.
+    no_dedup: bool,
+    #[arg(long)]
+    /// How many recent wallpapers to remember for dedup purposes.
+    history_size: Option<usize>,
+    #[arg(long)]
+    /// Preview the fetched wallpaper in the terminal and confirm before
+    /// assigning it. Has no effect with `--simple`.
+    preview: bool,
 }
 
 impl FetchArgs {
+    /// Overrides the category to fetch, used by `rotate` to cycle through a
+    /// list of categories without re-parsing CLI args each iteration.
+    pub(crate) fn with_category(mut self, category: Option<String>) -> Self {
+        self.category = category;
+        self
+    }
+
     pub async fn run(self) -> anyhow::Result<()> {
         let config = GlobalConfig::read()?;
         let category = {
@@ -48,25 +94,18 @@ impl FetchArgs {
                         .categories
                         .iter()
                         .map(|category| {
-                            let equality = category
-                                .name
-                                .chars()
-                                .map(|char| char.to_ascii_lowercase())
-                                .zip(category_name.chars().map(|char| char.to_ascii_lowercase()))
-                                .filter(|(a, b)| a == b)
-                                .count();
-                            (category, equality)
+                            let distance = levenshtein(&category.name, &category_name);
+                            (category, distance)
                         })
                         .collect::<Vec<_>>();
 
-                    categories
-                        .sort_by(|(_, simularity1), (_, simularity2)| simularity2.cmp(simularity1));
+                    categories.sort_by(|(_, distance1), (_, distance2)| distance1.cmp(distance2));
 
                     // Unwrap here, seeing that there being no entry in the array is checked earlier.
-                    let (best_category, simularity) = *categories.first().unwrap();
+                    let (best_category, distance) = *categories.first().unwrap();
 
-                    if simularity != category_name.len() {
-                        if simularity as f32 / category_name.len() as f32 >= 0.5 {
+                    if distance != 0 {
+                        if distance <= 1.max(category_name.len() / 3) {
                             bail!(
                                 "No category for name: {}, did you mean: {}?",
                                 category_name,
@@ -91,6 +130,23 @@ impl FetchArgs {
             }
         };
 
+        let process_options = ProcessOptions {
+            resize: self
+                .resize
+                .or(category.as_ref().and_then(|category| category.resize)),
+            fit: self
+                .fit
+                .or(category.as_ref().and_then(|category| category.fit))
+                .unwrap_or_default(),
+            blur: self
+                .blur
+                .or(category.as_ref().and_then(|category| category.blur)),
+            background: self
+                .background
+                .or(category.as_ref().and_then(|category| category.background))
+                .unwrap_or_default(),
+        };
+
         let parameters = {
             match category {
                 Some(category) => SearchParameters {
@@ -121,27 +177,20 @@ impl FetchArgs {
                         .suppliers
                         .iter()
                         .map(|category| {
-                            let equality = category
-                                .name
-                                .chars()
-                                .map(|char| char.to_ascii_lowercase())
-                                .zip(supplier_name.chars().map(|char| char.to_ascii_lowercase()))
-                                .filter(|(a, b)| a == b)
-                                .count();
-                            (category, equality)
+                            let distance = levenshtein(&category.name, &supplier_name);
+                            (category, distance)
                         })
                         .collect::<Vec<_>>();
 
-                    suppliers
-                        .sort_by(|(_, simularity1), (_, simularity2)| simularity1.cmp(simularity2));
+                    suppliers.sort_by(|(_, distance1), (_, distance2)| distance1.cmp(distance2));
 
                     // Unwrap here, seeing that there being no entry in the array is checked earlier.
-                    let (best_supplier, simularity) = *suppliers.first().unwrap();
+                    let (best_supplier, distance) = *suppliers.first().unwrap();
 
-                    if simularity != supplier_name.len() {
-                        if simularity as f32 / supplier_name.len() as f32 >= 0.5 {
+                    if distance != 0 {
+                        if distance <= 1.max(supplier_name.len() / 3) {
                             bail!(
-                                "No category for name: {}, did you mean: {}?",
+                                "No supplier for name: {}, did you mean: {}?",
                                 supplier_name,
                                 best_supplier.name
                             );
@@ -174,18 +223,60 @@ impl FetchArgs {
         };
         let supplier = ImageSupplier::new(url_supplier);
 
-        let image = if self.simple {
-            supplier.get_wallpaper_image(parameters).await?
+        let history_size = self
+            .history_size
+            .unwrap_or(config.history_size.unwrap_or(DEFAULT_HISTORY_SIZE));
+        let max_retries = config.dedup_retries.unwrap_or(DEFAULT_DEDUP_RETRIES);
+        let mut history = if self.no_dedup {
+            None
         } else {
-            let pb = ProgressBar::new_spinner();
-            pb.enable_steady_tick(Duration::from_millis(120));
-            pb.set_message("Downloading...");
-            let image = supplier.get_wallpaper_image(parameters).await?;
-            pb.finish_with_message("Downloaded");
+            Some(History::load()?)
+        };
 
-            image
+        let process = |image: Image| -> anyhow::Result<Image> {
+            if process_options.resize.is_some() || process_options.blur.is_some() {
+                image.process(&process_options)
+            } else {
+                Ok(image)
+            }
         };
 
+        let mut image = fetch_unseen(
+            &supplier,
+            parameters.clone(),
+            self.simple,
+            history.as_ref(),
+            max_retries,
+        )
+        .await?;
+        image = process(image)?;
+
+        if self.preview && !self.simple {
+            loop {
+                terminal::render(&image.decode()?)?;
+
+                if confirm("Use this wallpaper?")? {
+                    break;
+                }
+
+                println!("Skipping...");
+                let next = fetch_unseen(
+                    &supplier,
+                    parameters.clone(),
+                    self.simple,
+                    history.as_ref(),
+                    max_retries,
+                )
+                .await?;
+                image = process(next)?;
+            }
+        }
+
+        if let Some(history) = &mut history {
+            history.record(&image.decode()?, history_size);
+            history.save()?;
+        }
+
         let image_path = if let Some(output_file) = self.output {
             let pb = ProgressBar::new_spinner();
             pb.enable_steady_tick(Duration::from_millis(120));
@@ -240,4 +331,83 @@ impl FetchArgs {
 
         Ok(())
     }
+}
+
+/// Prompts `[Y/n]` on stdout and reads a line of the answer from stdin,
+/// defaulting to yes.
+fn confirm(prompt: &str) -> anyhow::Result<bool> {
+    print!("{} [Y/n] ", prompt);
+    std::io::stdout().flush()?;
+
+    let mut answer = String::new();
+    std::io::stdin().read_line(&mut answer)?;
+
+    Ok(!matches!(answer.trim().as_bytes().first(), Some(b'n' | b'N')))
+}
+
+/// Fetches a single wallpaper from `supplier`, showing a spinner unless
+/// `simple` output was requested.
+async fn fetch_one(
+    supplier: &ImageSupplier,
+    parameters: SearchParameters,
+    simple: bool,
+) -> anyhow::Result<Image> {
+    if simple {
+        supplier.get_wallpaper_image(parameters).await
+    } else {
+        let pb = ProgressBar::new_spinner();
+        pb.enable_steady_tick(Duration::from_millis(120));
+        pb.set_message("Downloading...");
+        let image = supplier.get_wallpaper_image(parameters).await?;
+        pb.finish_with_message("Downloaded");
+
+        Ok(image)
+    }
+}
+
+/// Fetches a wallpaper, re-querying `supplier` up to `max_retries` times
+/// while `history` says the candidate is a (near-)duplicate of something
+/// recently seen. `history` is `None` when `--no-dedup` was passed, in
+/// which case the first fetched candidate is always returned.
+async fn fetch_unseen(
+    supplier: &ImageSupplier,
+    parameters: SearchParameters,
+    simple: bool,
+    history: Option<&History>,
+    max_retries: usize,
+) -> anyhow::Result<Image> {
+    let mut image = fetch_one(supplier, parameters.clone(), simple).await?;
+
+    let Some(history) = history else {
+        return Ok(image);
+    };
+
+    let mut attempt = 0;
+    while history.has_seen(&image.decode()?) && attempt < max_retries {
+        attempt += 1;
+        image = fetch_one(supplier, parameters.clone(), simple).await?;
+    }
+
+    Ok(image)
+}
+
+/// Computes the Levenshtein edit distance between two strings, comparing
+/// case-insensitively so that e.g. "Anime" and "anim" score as close matches.
+fn levenshtein(a: &str, b: &str) -> usize {
+    let a = a.chars().flat_map(char::to_lowercase).collect::<Vec<_>>();
+    let b = b.chars().flat_map(char::to_lowercase).collect::<Vec<_>>();
+
+    let mut prev = (0..=b.len()).collect::<Vec<_>>();
+    let mut cur = vec![0; b.len() + 1];
+
+    for (i, a_char) in a.iter().enumerate() {
+        cur[0] = i + 1;
+        for (j, b_char) in b.iter().enumerate() {
+            let cost = if a_char == b_char { 0 } else { 1 };
+            cur[j + 1] = (cur[j] + 1).min(prev[j + 1] + 1).min(prev[j] + cost);
+        }
+        std::mem::swap(&mut prev, &mut cur);
+    }
+
+    prev[b.len()]
 }
\ No newline at end of file
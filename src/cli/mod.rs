@@ -0,0 +1,31 @@
+pub mod fetch;
+pub mod rotate;
+
+use clap::{Parser, Subcommand};
+
+use fetch::FetchArgs;
+use rotate::RotateArgs;
+
+#[derive(Parser, Debug)]
+#[command(name = "walltz", about = "Fetch and assign wallpapers")]
+pub struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Fetch (and optionally assign) a single wallpaper.
+    Fetch(FetchArgs),
+    /// Continuously re-fetch and re-assign wallpapers on an interval.
+    Rotate(RotateArgs),
+}
+
+impl Cli {
+    pub async fn run(self) -> anyhow::Result<()> {
+        match self.command {
+            Command::Fetch(args) => args.run().await,
+            Command::Rotate(args) => args.run().await,
+        }
+    }
+}
@@ -0,0 +1,84 @@
+use std::time::Duration;
+
+use clap::Args;
+use rand::seq::SliceRandom;
+
+use crate::config::GlobalConfig;
+
+use super::fetch::FetchArgs;
+
+#[derive(Args, Clone, Debug)]
+pub struct RotateArgs {
+    #[command(flatten)]
+    fetch: FetchArgs,
+    #[arg(short, long, value_parser = parse_duration)]
+    /// How long to wait between rotations, e.g. "30m", "1h", "45s". Defaults
+    /// to the config file's `rotate_every`.
+    every: Option<Duration>,
+    #[arg(long)]
+    /// Categories to cycle through, in order. Leave empty to keep reusing
+    /// `--category` (or no category, if unset) every iteration.
+    categories: Vec<String>,
+    #[arg(long)]
+    /// Pick the next category randomly instead of cycling in order.
+    random: bool,
+}
+
+impl RotateArgs {
+    pub async fn run(self) -> anyhow::Result<()> {
+        let config = GlobalConfig::read()?;
+        let every = self
+            .every
+            .or(config
+                .rotate_every
+                .as_deref()
+                .map(parse_duration)
+                .transpose()?)
+            .unwrap_or(Duration::from_secs(60 * 30));
+
+        let mut index = 0;
+        loop {
+            let attempt = if self.categories.is_empty() {
+                self.fetch.clone()
+            } else {
+                let category = if self.random {
+                    self.categories
+                        .choose(&mut rand::thread_rng())
+                        .unwrap()
+                        .clone()
+                } else {
+                    let category = self.categories[index % self.categories.len()].clone();
+                    index += 1;
+                    category
+                };
+
+                self.fetch.clone().with_category(Some(category))
+            };
+
+            if let Err(err) = attempt.run().await {
+                eprintln!("Rotate: fetch failed, will retry next interval: {:#}", err);
+            }
+
+            tokio::time::sleep(every).await;
+        }
+    }
+}
+
+/// Parses durations like "30s", "45m", "2h", "1d".
+fn parse_duration(s: &str) -> anyhow::Result<Duration> {
+    let (number, unit) = s.split_at(
+        s.find(|char: char| !char.is_ascii_digit())
+            .ok_or_else(|| anyhow::anyhow!("Expected a duration like \"30m\", got: {}", s))?,
+    );
+    let number: u64 = number.parse()?;
+
+    let seconds = match unit {
+        "s" => number,
+        "m" => number * 60,
+        "h" => number * 60 * 60,
+        "d" => number * 60 * 60 * 24,
+        other => anyhow::bail!("Unknown duration unit: {} (expected s, m, h or d)", other),
+    };
+
+    Ok(Duration::from_secs(seconds))
+}
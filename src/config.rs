@@ -0,0 +1,72 @@
+use std::path::PathBuf;
+
+use serde::Deserialize;
+
+use crate::image_processing::{Color, FitMode, Resolution};
+
+/// The user's global `walltz` configuration, read from `config.toml` in the
+/// config directory.
+#[derive(Debug, Clone, Deserialize)]
+pub struct GlobalConfig {
+    #[serde(default)]
+    pub categories: Vec<CategoryConfig>,
+    #[serde(default)]
+    pub suppliers: Vec<SupplierConfig>,
+    /// Command used to assign a wallpaper, with `{path}` substituted for the
+    /// downloaded image's path.
+    pub set_command: Option<String>,
+    /// Default dedup history size, overridable by `--history-size`.
+    pub history_size: Option<usize>,
+    /// How many times to re-query a supplier for a fresh wallpaper before
+    /// giving up and returning a duplicate.
+    pub dedup_retries: Option<usize>,
+    /// Default interval between `rotate` iterations, e.g. "30m", overridable
+    /// by `--every`.
+    pub rotate_every: Option<String>,
+}
+
+impl GlobalConfig {
+    /// Directory holding `config.toml` and any supplier files it references.
+    pub fn get_config_path() -> PathBuf {
+        dirs::config_dir()
+            .expect("Failed to determine config directory.")
+            .join("walltz")
+    }
+
+    pub fn read() -> anyhow::Result<Self> {
+        let path = Self::get_config_path().join("config.toml");
+        let content = std::fs::read_to_string(&path).map_err(|err| {
+            anyhow::anyhow!("Failed to read config file: {:?}, reason: {}", path, err)
+        })?;
+
+        Ok(toml::from_str(&content)?)
+    }
+}
+
+/// A predefined set of tags/aspect ratios the user can fetch by name, e.g.
+/// `walltz fetch --category anime`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct CategoryConfig {
+    pub name: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    pub aspect_ratios: Option<Vec<f32>>,
+    /// Default target resolution to fit fetched wallpapers to, overridable
+    /// by `--resize`.
+    pub resize: Option<Resolution>,
+    /// Default fit mode, overridable by `--fit`.
+    pub fit: Option<FitMode>,
+    /// Default Gaussian blur sigma, overridable by `--blur`.
+    pub blur: Option<f32>,
+    /// Default padding color for `FitMode::Contain`, overridable by
+    /// `--background`. Defaults to opaque black.
+    pub background: Option<Color>,
+}
+
+/// Points at a supplier definition file, kept separate from `config.toml` so
+/// suppliers can be shared/swapped without touching the main config.
+#[derive(Debug, Clone, Deserialize)]
+pub struct SupplierConfig {
+    pub name: String,
+    pub file: PathBuf,
+}
@@ -0,0 +1,96 @@
+use std::{collections::VecDeque, fs};
+
+use image::{imageops::FilterType, DynamicImage};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::config::GlobalConfig;
+
+/// Max Hamming distance between average hashes still considered a
+/// near-duplicate.
+const NEAR_DUPLICATE_THRESHOLD: u32 = 5;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Entry {
+    /// SHA-256 of the decoded pixel buffer, catches exact re-downloads.
+    hash: String,
+    /// 8x8 average hash, catches re-encodes/resizes of the same image.
+    average_hash: u64,
+}
+
+/// A ring of recently seen wallpapers, persisted under the config dir so
+/// repeated `fetch` runs don't keep returning the same image.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct History {
+    entries: VecDeque<Entry>,
+}
+
+impl History {
+    fn path() -> std::path::PathBuf {
+        GlobalConfig::get_config_path().join("history.json")
+    }
+
+    pub fn load() -> anyhow::Result<Self> {
+        match fs::read_to_string(Self::path()) {
+            Ok(content) => Ok(serde_json::from_str(&content)?),
+            Err(_) => Ok(Self::default()),
+        }
+    }
+
+    pub fn save(&self) -> anyhow::Result<()> {
+        let path = Self::path();
+        fs::create_dir_all(path.parent().unwrap())?;
+        fs::write(path, serde_json::to_string_pretty(self)?)?;
+
+        Ok(())
+    }
+
+    /// Whether `image` (or a near-duplicate) is already in the history.
+    pub fn has_seen(&self, image: &DynamicImage) -> bool {
+        let hash = content_hash(image);
+        let average_hash = average_hash(image);
+
+        self.entries.iter().any(|entry| {
+            entry.hash == hash || hamming_distance(entry.average_hash, average_hash) <= NEAR_DUPLICATE_THRESHOLD
+        })
+    }
+
+    /// Records `image`, trimming the history down to `history_size` entries.
+    pub fn record(&mut self, image: &DynamicImage, history_size: usize) {
+        self.entries.push_back(Entry {
+            hash: content_hash(image),
+            average_hash: average_hash(image),
+        });
+
+        while self.entries.len() > history_size {
+            self.entries.pop_front();
+        }
+    }
+}
+
+fn content_hash(image: &DynamicImage) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(image.to_rgba8().as_raw());
+
+    format!("{:x}", hasher.finalize())
+}
+
+/// Downscales to 8x8 grayscale and thresholds against the mean, giving a
+/// hash that's stable across re-encodes and minor resizes.
+fn average_hash(image: &DynamicImage) -> u64 {
+    let small = image
+        .resize_exact(8, 8, FilterType::Triangle)
+        .to_luma8();
+    let pixels = small.as_raw();
+    let mean = pixels.iter().map(|&pixel| pixel as u32).sum::<u32>() / pixels.len() as u32;
+
+    pixels
+        .iter()
+        .enumerate()
+        .filter(|(_, &pixel)| pixel as u32 >= mean)
+        .fold(0u64, |hash, (i, _)| hash | (1 << i))
+}
+
+fn hamming_distance(a: u64, b: u64) -> u32 {
+    (a ^ b).count_ones()
+}
@@ -0,0 +1,136 @@
+use image::DynamicImage;
+
+/// The real encoding of a downloaded wallpaper, sniffed from its magic
+/// bytes rather than trusted from the supplier's URL extension.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DetectedFormat {
+    /// Anything the `image` crate already handles directly (PNG, JPEG, …).
+    Standard,
+    Heif,
+    Raw,
+}
+
+/// ISO base media file format brands that mark a HEIF/AVIF container.
+const HEIF_BRANDS: &[&[u8; 4]] = &[
+    b"heic", b"heix", b"hevc", b"heim", b"heis", b"mif1", b"msf1", b"avif", b"avis",
+];
+
+pub fn detect(data: &[u8]) -> DetectedFormat {
+    if data.len() >= 12
+        && &data[4..8] == b"ftyp"
+        && HEIF_BRANDS.iter().any(|brand| &data[8..12] == *brand)
+    {
+        return DetectedFormat::Heif;
+    }
+
+    if is_raw(data) {
+        return DetectedFormat::Raw;
+    }
+
+    DetectedFormat::Standard
+}
+
+/// The TIFF tag (an EXIF `DNGVersion`) present in every DNG's IFD0.
+const DNG_VERSION_TAG: u16 = 0xC612;
+
+/// Recognizes CR2 and DNG, which wrap a TIFF container, by a TIFF magic
+/// number plus a RAW-specific marker. Other RAW formats (NEF, ARW, CR3, ...)
+/// are not recognized and are reported as `Standard`.
+fn is_raw(data: &[u8]) -> bool {
+    let is_tiff_container = data.starts_with(b"II*\0") || data.starts_with(b"MM\0*");
+    if !is_tiff_container {
+        return false;
+    }
+
+    // CR2 stamps its own magic right after the TIFF header, at a fixed
+    // offset, rather than anywhere in the file.
+    data.get(8..12) == Some(b"CR\x02\0".as_slice()) || tiff_has_tag(data, DNG_VERSION_TAG)
+}
+
+/// Whether IFD0 of a TIFF container contains an entry for `tag`, per the
+/// TIFF6 layout: a header pointing at an IFD of 12-byte `(tag, type, count,
+/// value/offset)` entries.
+fn tiff_has_tag(data: &[u8], tag: u16) -> bool {
+    let little_endian = data.starts_with(b"II");
+
+    let read_u16 = |offset: usize| -> Option<u16> {
+        let bytes = data.get(offset..offset + 2)?.try_into().ok()?;
+        Some(if little_endian {
+            u16::from_le_bytes(bytes)
+        } else {
+            u16::from_be_bytes(bytes)
+        })
+    };
+    let read_u32 = |offset: usize| -> Option<u32> {
+        let bytes = data.get(offset..offset + 4)?.try_into().ok()?;
+        Some(if little_endian {
+            u32::from_le_bytes(bytes)
+        } else {
+            u32::from_be_bytes(bytes)
+        })
+    };
+
+    let Some(ifd_offset) = read_u32(4).map(|offset| offset as usize) else {
+        return false;
+    };
+    let Some(entry_count) = read_u16(ifd_offset).map(|count| count as usize) else {
+        return false;
+    };
+
+    (0..entry_count).any(|i| read_u16(ifd_offset + 2 + i * 12) == Some(tag))
+}
+
+/// Decodes a HEIF/AVIF payload via libheif. Requires the `heif` feature;
+/// without it, wallpapers in this format are reported as unsupported
+/// rather than silently failing the generic decoder.
+#[cfg(feature = "heif")]
+pub fn decode_heif(data: &[u8]) -> anyhow::Result<DynamicImage> {
+    use libheif_rs::{ColorSpace, HeifContext, RgbChroma};
+
+    let context = HeifContext::read_from_bytes(data)?;
+    let handle = context.primary_image_handle()?;
+    let heif_image = handle.decode(ColorSpace::Rgb(RgbChroma::Rgb), None, false)?;
+    let plane = heif_image
+        .planes()
+        .interleaved
+        .ok_or(anyhow::anyhow!("Decoded HEIF image had no interleaved RGB plane."))?;
+
+    let buffer = image::RgbImage::from_raw(
+        plane.width,
+        plane.height,
+        plane.data.to_vec(),
+    )
+    .ok_or(anyhow::anyhow!("Failed to build RGB buffer from decoded HEIF data."))?;
+
+    Ok(DynamicImage::ImageRgb8(buffer))
+}
+
+#[cfg(not(feature = "heif"))]
+pub fn decode_heif(_data: &[u8]) -> anyhow::Result<DynamicImage> {
+    anyhow::bail!(
+        "This wallpaper is HEIF/AVIF-encoded; rebuild walltz with the \"heif\" feature to decode it."
+    )
+}
+
+/// Demosaics a camera RAW payload into an RGB buffer via rawloader +
+/// imagepipe. Requires the `raw` feature.
+#[cfg(feature = "raw")]
+pub fn decode_raw(data: &[u8]) -> anyhow::Result<DynamicImage> {
+    let raw_image = rawloader::decode(&mut std::io::Cursor::new(data))?;
+    let pipeline = imagepipe::Pipeline::new_from_source(imagepipe::ImageSource::Raw(raw_image))?;
+    let decoded = pipeline.output_8bit(None)?;
+
+    let buffer = image::RgbImage::from_raw(decoded.width as u32, decoded.height as u32, decoded.data)
+        .ok_or(anyhow::anyhow!(
+            "Failed to build RGB buffer from demosaiced RAW data."
+        ))?;
+
+    Ok(DynamicImage::ImageRgb8(buffer))
+}
+
+#[cfg(not(feature = "raw"))]
+pub fn decode_raw(_data: &[u8]) -> anyhow::Result<DynamicImage> {
+    anyhow::bail!(
+        "This wallpaper is a camera RAW file; rebuild walltz with the \"raw\" feature to decode it."
+    )
+}
@@ -0,0 +1,170 @@
+use clap::ValueEnum;
+use image::{imageops::FilterType, DynamicImage, Rgba};
+use serde::Deserialize;
+
+/// How a decoded image should be fit to a target resolution.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum, Deserialize)]
+#[clap(rename_all = "kebab-case")]
+#[serde(rename_all = "kebab-case")]
+pub enum FitMode {
+    /// Scale so the smaller dimension matches the target, then center-crop.
+    #[default]
+    Cover,
+    /// Scale so the larger dimension matches the target, then pad.
+    Contain,
+    /// Scale both dimensions independently, ignoring aspect ratio.
+    Stretch,
+}
+
+/// An RGBA color, parsed from a hex CLI flag like `000000` or `00000080`
+/// (alpha defaults to opaque if omitted).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "String")]
+pub struct Color {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+    pub a: u8,
+}
+
+impl Color {
+    pub const BLACK: Color = Color {
+        r: 0,
+        g: 0,
+        b: 0,
+        a: 255,
+    };
+
+    fn to_rgba(self) -> Rgba<u8> {
+        Rgba([self.r, self.g, self.b, self.a])
+    }
+}
+
+impl Default for Color {
+    fn default() -> Self {
+        Self::BLACK
+    }
+}
+
+impl std::str::FromStr for Color {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let hex = s.strip_prefix('#').unwrap_or(s);
+        let byte = |i: usize| -> anyhow::Result<u8> {
+            u8::from_str_radix(
+                hex.get(i..i + 2)
+                    .ok_or_else(|| anyhow::anyhow!("Expected a hex color like \"RRGGBB\" or \"RRGGBBAA\", got: {}", s))?,
+                16,
+            )
+            .map_err(|_| anyhow::anyhow!("Expected a hex color like \"RRGGBB\" or \"RRGGBBAA\", got: {}", s))
+        };
+
+        match hex.len() {
+            6 => Ok(Self {
+                r: byte(0)?,
+                g: byte(2)?,
+                b: byte(4)?,
+                a: 255,
+            }),
+            8 => Ok(Self {
+                r: byte(0)?,
+                g: byte(2)?,
+                b: byte(4)?,
+                a: byte(6)?,
+            }),
+            _ => anyhow::bail!("Expected a hex color like \"RRGGBB\" or \"RRGGBBAA\", got: {}", s),
+        }
+    }
+}
+
+impl TryFrom<String> for Color {
+    type Error = anyhow::Error;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+/// A `width x height` target, parsed from a CLI flag like `1920x1080`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "String")]
+pub struct Resolution {
+    pub width: u32,
+    pub height: u32,
+}
+
+impl std::str::FromStr for Resolution {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (width, height) = s
+            .split_once('x')
+            .ok_or_else(|| anyhow::anyhow!("Expected a resolution in WxH form, got: {}", s))?;
+
+        Ok(Self {
+            width: width.parse()?,
+            height: height.parse()?,
+        })
+    }
+}
+
+impl TryFrom<String> for Resolution {
+    type Error = anyhow::Error;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+/// Post-processing to apply to a fetched wallpaper before it's saved,
+/// resolved from CLI flags overriding the active category's config.
+#[derive(Debug, Clone, Default)]
+pub struct ProcessOptions {
+    pub resize: Option<Resolution>,
+    pub fit: FitMode,
+    pub blur: Option<f32>,
+    /// Canvas color used to pad the image under `FitMode::Contain`.
+    pub background: Color,
+}
+
+/// Resizes `image` per `options.fit`/`options.resize` and applies an
+/// optional Gaussian blur pass, in that order.
+pub fn process(image: DynamicImage, options: &ProcessOptions) -> DynamicImage {
+    let mut image = match options.resize {
+        Some(target) => match options.fit {
+            FitMode::Cover => {
+                image.resize_to_fill(target.width, target.height, FilterType::Lanczos3)
+            }
+            FitMode::Contain => pad_to(
+                image.resize(target.width, target.height, FilterType::Lanczos3),
+                target,
+                options.background,
+            ),
+            FitMode::Stretch => {
+                image.resize_exact(target.width, target.height, FilterType::Lanczos3)
+            }
+        },
+        None => image,
+    };
+
+    if let Some(sigma) = options.blur {
+        image = DynamicImage::ImageRgba8(image::imageops::blur(&image, sigma));
+    }
+
+    image
+}
+
+/// Centers `image` on a `background`-colored canvas of `target` size.
+fn pad_to(image: DynamicImage, target: Resolution, background: Color) -> DynamicImage {
+    let mut canvas = DynamicImage::new_rgba8(target.width, target.height);
+    for pixel in canvas.as_mut_rgba8().unwrap().pixels_mut() {
+        *pixel = background.to_rgba();
+    }
+
+    let x = (target.width.saturating_sub(image.width())) / 2;
+    let y = (target.height.saturating_sub(image.height())) / 2;
+    image::imageops::overlay(&mut canvas, &image, x as i64, y as i64);
+
+    canvas
+}
@@ -0,0 +1,282 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::OnceLock,
+};
+
+use anyhow::{anyhow, Context};
+use rand::seq::SliceRandom;
+use serde::Deserialize;
+
+use crate::format_detect::{self, DetectedFormat};
+use crate::image_processing::{self, ProcessOptions};
+
+/// Tags and aspect ratio constraints a supplier is asked to satisfy.
+#[derive(Debug, Clone, Default)]
+pub struct SearchParameters {
+    pub tags: Vec<String>,
+    pub aspect_ratios: Vec<f32>,
+}
+
+/// A downloaded (or loaded) wallpaper, ready to be written out or assigned.
+pub struct Image {
+    bytes: Vec<u8>,
+    extension: String,
+}
+
+impl Image {
+    pub fn from_bytes(bytes: Vec<u8>, extension: impl Into<String>) -> Self {
+        Self {
+            bytes,
+            extension: extension.into(),
+        }
+    }
+
+    /// Decodes the image's bytes into a [`DynamicImage`](image::DynamicImage).
+    ///
+    /// The real format is sniffed from magic bytes rather than trusted from
+    /// `extension`, since suppliers often hide HEIF/AVIF or RAW payloads
+    /// behind an unrelated URL extension.
+    pub fn decode(&self) -> anyhow::Result<image::DynamicImage> {
+        match format_detect::detect(&self.bytes) {
+            DetectedFormat::Heif => format_detect::decode_heif(&self.bytes),
+            DetectedFormat::Raw => format_detect::decode_raw(&self.bytes),
+            DetectedFormat::Standard => Ok(image::load_from_memory(&self.bytes)?),
+        }
+    }
+
+    /// Applies resize/fit/blur post-processing, returning the re-encoded
+    /// result as a new [`Image`].
+    pub fn process(&self, options: &ProcessOptions) -> anyhow::Result<Image> {
+        let decoded = self.decode()?;
+        let processed = image_processing::process(decoded, options);
+
+        let mut bytes = vec![];
+        processed.write_to(
+            &mut std::io::Cursor::new(&mut bytes),
+            image::ImageFormat::Png,
+        )?;
+
+        Ok(Image::from_bytes(bytes, "png"))
+    }
+
+    /// Writes the image to `path`, inferring the output format from `path`'s
+    /// extension via the `image` crate so the on-disk bytes always match the
+    /// file's extension, not the source's.
+    pub fn save_to_format(&self, path: &Path) -> anyhow::Result<()> {
+        self.decode()?.save(path)?;
+
+        Ok(())
+    }
+
+    /// Writes the image to the cache directory as `wallpaper.<extension>`,
+    /// replacing any previously cached wallpaper, and returns its path.
+    ///
+    /// Goes through `decode()` rather than writing `self.bytes` as-is, so a
+    /// supplier that mislabels a HEIF/AVIF/RAW payload's content-type still
+    /// ends up with a real, decodable image on disk instead of raw bytes
+    /// under a lying extension.
+    pub fn cache(&self) -> anyhow::Result<PathBuf> {
+        let cache_dir = dirs::cache_dir()
+            .ok_or(anyhow!("Failed to determine cache directory."))?
+            .join("walltz");
+        fs::create_dir_all(&cache_dir)?;
+
+        let path = cache_dir.join(format!("wallpaper.{}", self.extension));
+        self.decode()?.save(&path)?;
+
+        Ok(path)
+    }
+}
+
+/// Where `fetch` should draw wallpapers from. Deserialized from a supplier
+/// file referenced by `SupplierConfig::file`.
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum UrlSupplier {
+    Http(HttpSupplier),
+    /// Serves wallpapers from one or more local directories instead of a
+    /// remote API.
+    Local(LocalSupplier),
+}
+
+/// A remote HTTP API supplier that requests `base_url` as given; the search
+/// tags and aspect ratios are not sent.
+#[derive(Debug, Deserialize)]
+pub struct HttpSupplier {
+    pub base_url: String,
+    pub api_key: Option<String>,
+}
+
+impl HttpSupplier {
+    async fn get_wallpaper_image(&self, _parameters: SearchParameters) -> anyhow::Result<Image> {
+        let mut request = reqwest::Client::new().get(&self.base_url);
+        if let Some(api_key) = &self.api_key {
+            request = request.bearer_auth(api_key);
+        }
+
+        let response = request.send().await?;
+        let extension = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.split('/').next_back())
+            .unwrap_or("png")
+            .to_owned();
+        let bytes = response.bytes().await?.to_vec();
+
+        Ok(Image::from_bytes(bytes, extension))
+    }
+}
+
+/// Serves wallpapers from local directories, matching tags against
+/// path/filename components and aspect ratio against decoded dimensions.
+#[derive(Debug, Deserialize)]
+pub struct LocalSupplier {
+    pub directories: Vec<PathBuf>,
+    /// Lazily built and cached on first use, since a `fetch` invocation may
+    /// call `index` repeatedly (dedup retries, preview rejections).
+    #[serde(skip)]
+    index_cache: OnceLock<Vec<PathBuf>>,
+}
+
+const IMAGE_EXTENSIONS: &[&str] = &["png", "jpg", "jpeg", "webp", "bmp", "gif"];
+
+impl LocalSupplier {
+    /// Walks all configured directories on first use and caches the result;
+    /// subsequent calls return the cached index.
+    fn index(&self) -> anyhow::Result<&[PathBuf]> {
+        if let Some(index) = self.index_cache.get() {
+            return Ok(index);
+        }
+
+        let mut index = vec![];
+
+        for root in &self.directories {
+            let mut stack = vec![root.clone()];
+            while let Some(dir) = stack.pop() {
+                let entries = fs::read_dir(&dir)
+                    .with_context(|| format!("Failed to read directory: {:?}", dir))?;
+
+                for entry in entries {
+                    let entry = entry?;
+                    let path = entry.path();
+
+                    if path.is_dir() {
+                        stack.push(path);
+                        continue;
+                    }
+
+                    let is_image = path
+                        .extension()
+                        .and_then(|ext| ext.to_str())
+                        .map(|ext| IMAGE_EXTENSIONS.contains(&ext.to_ascii_lowercase().as_str()))
+                        .unwrap_or(false);
+
+                    if is_image {
+                        index.push(path);
+                    }
+                }
+            }
+        }
+
+        Ok(self.index_cache.get_or_init(|| index))
+    }
+
+    async fn get_wallpaper_image(&self, parameters: SearchParameters) -> anyhow::Result<Image> {
+        let index = self.index()?;
+
+        if index.is_empty() {
+            return Err(anyhow!(
+                "No images found in configured directories: {:?}",
+                self.directories
+            ));
+        }
+
+        let candidates = if parameters.tags.is_empty() {
+            index.iter().map(|path| (path, 0)).collect::<Vec<_>>()
+        } else {
+            let tags = parameters
+                .tags
+                .iter()
+                .map(|tag| tag.to_ascii_lowercase())
+                .collect::<Vec<_>>();
+
+            let scored = index
+                .iter()
+                .map(|path| {
+                    let haystack = path.to_string_lossy().to_ascii_lowercase();
+                    let matches = tags.iter().filter(|tag| haystack.contains(*tag)).count();
+                    (path, matches)
+                })
+                .filter(|(_, matches)| *matches > 0)
+                .collect::<Vec<_>>();
+
+            let best = scored.iter().map(|(_, matches)| *matches).max();
+            match best {
+                Some(best) => scored
+                    .into_iter()
+                    .filter(|(_, matches)| *matches == best)
+                    .collect(),
+                None => return Err(anyhow!("No local images matched tags: {:?}", tags)),
+            }
+        };
+
+        let candidates = if parameters.aspect_ratios.is_empty() {
+            candidates
+        } else {
+            let matching = candidates
+                .into_iter()
+                .filter(|(path, _)| {
+                    image::image_dimensions(path)
+                        .map(|(width, height)| {
+                            let ratio = width as f32 / height as f32;
+                            parameters
+                                .aspect_ratios
+                                .iter()
+                                .any(|target| (ratio - target).abs() < 0.05)
+                        })
+                        .unwrap_or(false)
+                })
+                .collect::<Vec<_>>();
+
+            if matching.is_empty() {
+                return Err(anyhow!("No local images matched the requested aspect ratio."));
+            }
+
+            matching
+        };
+
+        let (path, _) = candidates
+            .choose(&mut rand::thread_rng())
+            .ok_or(anyhow!("No local images matched the search parameters."))?;
+
+        let bytes = fs::read(path)?;
+        let extension = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .unwrap_or("png")
+            .to_owned();
+
+        Ok(Image::from_bytes(bytes, extension))
+    }
+}
+
+/// Wraps a configured [`UrlSupplier`] and dispatches `fetch` to whichever
+/// backend it names.
+pub struct ImageSupplier {
+    supplier: UrlSupplier,
+}
+
+impl ImageSupplier {
+    pub fn new(supplier: UrlSupplier) -> Self {
+        Self { supplier }
+    }
+
+    pub async fn get_wallpaper_image(&self, parameters: SearchParameters) -> anyhow::Result<Image> {
+        match &self.supplier {
+            UrlSupplier::Http(supplier) => supplier.get_wallpaper_image(parameters).await,
+            UrlSupplier::Local(supplier) => supplier.get_wallpaper_image(parameters).await,
+        }
+    }
+}
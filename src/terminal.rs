@@ -0,0 +1,100 @@
+use std::io::Write;
+
+use base64::Engine;
+use image::{imageops::FilterType, DynamicImage};
+
+/// Renders `image` directly in the terminal: the Kitty or iTerm2 graphics
+/// protocol when detected, otherwise half-block Unicode cells with 24-bit
+/// color.
+pub fn render(image: &DynamicImage) -> anyhow::Result<()> {
+    if is_kitty() {
+        render_kitty(image)
+    } else if is_iterm() {
+        render_iterm(image)
+    } else {
+        render_half_blocks(image)
+    }
+}
+
+fn is_kitty() -> bool {
+    std::env::var("TERM")
+        .map(|term| term.contains("kitty"))
+        .unwrap_or(false)
+        || std::env::var("KITTY_WINDOW_ID").is_ok()
+}
+
+fn is_iterm() -> bool {
+    std::env::var("TERM_PROGRAM")
+        .map(|program| program == "iTerm.app")
+        .unwrap_or(false)
+}
+
+fn encode_png(image: &DynamicImage) -> anyhow::Result<Vec<u8>> {
+    let mut bytes = vec![];
+    image.write_to(&mut std::io::Cursor::new(&mut bytes), image::ImageFormat::Png)?;
+
+    Ok(bytes)
+}
+
+/// Writes `image` using the Kitty graphics protocol, chunking the base64
+/// payload as the protocol requires.
+fn render_kitty(image: &DynamicImage) -> anyhow::Result<()> {
+    let encoded = base64::engine::general_purpose::STANDARD.encode(encode_png(image)?);
+    let chunks = encoded.as_bytes().chunks(4096).collect::<Vec<_>>();
+
+    for (i, chunk) in chunks.iter().enumerate() {
+        let more = if i + 1 == chunks.len() { 0 } else { 1 };
+        let control = if i == 0 {
+            format!("a=T,f=100,m={}", more)
+        } else {
+            format!("m={}", more)
+        };
+        print!("\x1b_G{};{}\x1b\\", control, std::str::from_utf8(chunk)?);
+    }
+    println!();
+    std::io::stdout().flush()?;
+
+    Ok(())
+}
+
+/// Writes `image` using the iTerm2 inline image protocol.
+fn render_iterm(image: &DynamicImage) -> anyhow::Result<()> {
+    let encoded = base64::engine::general_purpose::STANDARD.encode(encode_png(image)?);
+    println!(
+        "\x1b]1337;File=inline=1;width=auto;height=auto;preserveAspectRatio=1:{}\x07",
+        encoded
+    );
+    std::io::stdout().flush()?;
+
+    Ok(())
+}
+
+/// Downscales `image` to the terminal's cell grid (two vertical pixels per
+/// cell via the upper-half-block glyph) and prints it with 24-bit color.
+fn render_half_blocks(image: &DynamicImage) -> anyhow::Result<()> {
+    let (columns, rows) = terminal_size::terminal_size()
+        .map(|(width, height)| (width.0 as u32, height.0 as u32))
+        .unwrap_or((80, 24));
+
+    let width = columns.max(1);
+    let height = rows.saturating_sub(2).max(1) * 2;
+
+    let resized = image
+        .resize_exact(width, height, FilterType::Triangle)
+        .to_rgb8();
+
+    for y in (0..height).step_by(2) {
+        for x in 0..width {
+            let top = resized.get_pixel(x, y);
+            let bottom = resized.get_pixel(x, (y + 1).min(height - 1));
+            print!(
+                "\x1b[38;2;{};{};{}m\x1b[48;2;{};{};{}m\u{2580}",
+                top[0], top[1], top[2], bottom[0], bottom[1], bottom[2]
+            );
+        }
+        println!("\x1b[0m");
+    }
+    std::io::stdout().flush()?;
+
+    Ok(())
+}